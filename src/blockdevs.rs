@@ -1,4 +1,5 @@
 use crate::{ItRes, LsblkError, Res};
+use std::collections::HashMap;
 use std::os::linux::fs::MetadataExt;
 use std::path::{Path, PathBuf};
 
@@ -21,97 +22,134 @@ pub(crate) fn ls_symlinks(dir: &Path) -> Res<Box<ItRes<(PathBuf, String)>>> {
     })
 }
 
+fn u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn u64_le(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes([
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7],
+    ])
+}
+
+/// Format a raw 16-byte GPT partition type GUID as the usual mixed-endian hyphenated string,
+/// e.g. `c12a7328-f81f-11d2-ba4b-00a0c93ec93b`.
+fn format_guid(bytes: &[u8; 16]) -> String {
+    format!(
+        "{:02x}{:02x}{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}-{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
+        bytes[3], bytes[2], bytes[1], bytes[0],
+        bytes[5], bytes[4],
+        bytes[7], bytes[6],
+        bytes[8], bytes[9],
+        bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15],
+    )
+}
+
+/// Read as many bytes as available into `buf`, stopping short of an error on a device that's
+/// smaller than `buf` rather than failing outright (mirrors `Read::read_to_end` but bounded).
+fn read_as_much_as_possible(f: &mut std::fs::File, buf: &mut [u8]) -> std::io::Result<usize> {
+    use std::io::Read;
+    let mut total = 0;
+    while total < buf.len() {
+        match f.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(n) => total += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(total)
+}
+
 /// A representation of a block-device
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BlockDevice {
     /// the filename of the block-device.
     pub name: String,
     /// The full name of the block-device, which is basically `/dev/{name}`.
+    #[cfg_attr(feature = "serde", serde(rename = "path"))]
     pub fullname: PathBuf,
     /// The diskseq of the device as in `/dev/disk/by-diskseq/`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub diskseq: Option<String>,
     /// The path (not the filesystem!) of the device as in `/dev/disk/by-path`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "by_path", skip_serializing_if = "Option::is_none")
+    )]
     pub path: Option<String>,
     /// The device UUID.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub uuid: Option<String>,
     /// The UUID of a partition (not the same as device UUID).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub partuuid: Option<String>,
     /// The label of the partition.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub label: Option<String>,
     /// The partition label (not the same as `label`), as in `/dev/disk/by-partlabel`)
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub partlabel: Option<String>,
     /// The id of the device as in `/dev/disk/by-id/`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub id: Option<String>,
+    /// The GPT partition type GUID, e.g. `c12a7328-f81f-11d2-ba4b-00a0c93ec93b` for an EFI
+    /// System Partition. Only set for partitions on a GPT-partitioned disk.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub parttype: Option<String>,
+    /// The on-disk filesystem type, detected by probing well-known superblock signatures (e.g.
+    /// `ext4`, `xfs`, `btrfs`, `vfat`, `swap`).
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub fstype: Option<String>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct BlockDevices<'a> {
+    blockdevices: &'a [BlockDevice],
+}
+
+#[cfg(feature = "serde")]
+impl BlockDevice {
+    /// Serialize a list of devices the way `lsblk -J` does: a top-level
+    /// `{"blockdevices": [...]}` object, so the crate can be a drop-in replacement for parsing
+    /// `lsblk -J` output in existing tooling.
+    ///
+    /// # Errors
+    /// Returned if the underlying JSON serialization fails.
+    pub fn to_json(devs: &[Self]) -> serde_json::Result<String> {
+        serde_json::to_string(&BlockDevices { blockdevices: devs })
+    }
 }
 
 impl BlockDevice {
     /// List out all found block devices and populate all fields.
     ///
+    /// This builds a throwaway [`crate::DiskManage`] internally; if you're listing or resolving
+    /// more than one device, construct your own [`crate::DiskManage`] instead so the
+    /// `/dev/disk/by-*` scans are shared across calls.
+    ///
+    /// [`Self::parttype`] and [`Self::fstype`] are not populated here; use
+    /// [`crate::PopulateDisk`] for those.
+    ///
     /// # Panics
     /// If somehow there exists a device that isn't in `/dev/`, the function panics.
     ///
     /// # Errors
     /// There are no particular errors other than IO / symlink resolution failures, etc.
     pub fn list() -> Result<Vec<Self>, LsblkError> {
-        let mut result = std::collections::HashMap::new();
-        macro_rules! insert {
-            ($kind:ident) => {
-                for x in ls_symlinks(Path::new(concat!("/dev/disk/by-", stringify!($kind))))? {
-                    let (fullname, blk) = x?;
-                    let name = fullname
-                        .strip_prefix("/dev/")
-                        .expect("Cannot strip /dev")
-                        .to_string_lossy()
-                        .to_string();
-                    if let Some(bd) = result.get_mut(&name) {
-                        bd.$kind = Some(blk);
-                    } else {
-                        result.insert(
-                            name.to_string(),
-                            Self {
-                                name,
-                                fullname,
-                                $kind: Some(blk),
-                                ..Self::default()
-                            },
-                        );
-                    }
-                }
-            };
-        }
-        for x in ls_symlinks(Path::new("/dev/disk/by-diskseq/"))? {
-            let (fullname, blk) = x?;
-            let name = fullname
-                .strip_prefix("/dev/")
-                .expect("Cannot strip /dev")
-                .to_string_lossy()
-                .to_string();
-            result.insert(
-                name.to_string(), // FIXME: clone shouldn't be needed theoretically
-                Self {
-                    name,
-                    fullname,
-                    diskseq: Some(blk),
-                    ..Self::default()
-                },
-            );
-        }
-        insert!(path);
-        insert!(uuid);
-        insert!(partuuid);
-        insert!(label);
-        insert!(partlabel);
-        insert!(id);
-        Ok(result.into_values().collect())
+        crate::DiskManage::new().list()
     }
 
     /// Create a [`BlockDevice`] from a path that is either `/dev/{name}` or a path to a (sym)link
     /// that points to `/dev/{name}`.
     ///
-    /// Note that this function is rather expensive (because it needs to list out all links in
-    /// `/dev/disks/by-diskseq/` and other directories in the worst case scenario to find the one
-    /// that links to `/dev/{name}`). Therefore, you should prefer [`BlockDevice::list()`] instead
-    /// if you would like to list out more than 1 blockdevice.
+    /// This builds a throwaway [`crate::DiskManage`] internally (because it needs to list out
+    /// all links in `/dev/disk/by-diskseq/` and other directories in the worst case scenario to
+    /// find the one that links to `/dev/{name}`). Therefore, you should prefer
+    /// [`BlockDevice::list()`], or construct your own [`crate::DiskManage`], if you would like to
+    /// resolve more than 1 block-device.
     ///
     /// If you would like to not populate all fields for now, use
     /// [`BlockDevice::from_path_unpopulated()`] instead.
@@ -122,27 +160,7 @@ impl BlockDevice {
     /// # Errors
     /// There are no particular errors other than IO / symlink resolution failures, etc.
     pub fn from_path<P: AsRef<Path>>(p: P) -> Result<Self, LsblkError> {
-        let pathbuf = (p.as_ref().canonicalize())
-            .map_err(|e| LsblkError::BadSymlink(p.as_ref().to_owned(), e))?;
-        let mut res = Self::from_abs_path_unpopulated(pathbuf.clone());
-        macro_rules! insert {
-            ($kind:ident) => {
-                if let Some(Ok((_, blk))) =
-                    ls_symlinks(Path::new(concat!("/dev/disk/by-", stringify!($kind))))?
-                        .find(|elm| elm.as_ref().is_ok_and(|(fullname, _)| fullname == &pathbuf))
-                {
-                    res.$kind = Some(blk);
-                }
-            };
-        }
-        insert!(diskseq);
-        insert!(path);
-        insert!(uuid);
-        insert!(partuuid);
-        insert!(label);
-        insert!(partlabel);
-        insert!(id);
-        Ok(res)
+        crate::DiskManage::new().from_path(p)
     }
 
     /// Create a [`BlockDevice`] from a path that is either `/dev/{name}` or a path to a (sym)link
@@ -245,6 +263,20 @@ impl BlockDevice {
         Ok((major as u32, minor as u32)) // guaranteed by bit filters
     }
 
+    /// Whether this device's own sysfs entry carries a `partition` marker file — the same signal
+    /// [`Self::partitions`] uses to find a disk's children.
+    ///
+    /// Unlike [`Self::is_part`] (which just checks whether `partuuid` happens to be populated),
+    /// this also works for a [`Self`] built via [`Self::from_abs_path_unpopulated`] (e.g. from
+    /// [`Self::holders`], [`Self::slaves`], or [`Self::partitions`] itself), none of which
+    /// populate `partuuid`.
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    fn is_sysfs_partition(&self) -> std::io::Result<bool> {
+        Ok(self.sysfs()?.join("partition").exists())
+    }
+
     /// If the block-device is a partition, look up the parent disk in sysfs and return its
     /// name. Otherwise, returns [`BlockDevice::name`] if not a partition.
     ///
@@ -254,7 +286,7 @@ impl BlockDevice {
     /// # Panics
     /// A panic will be raised if the disk name is not UTF-8 compliant or if the parent path is invalid
     pub fn disk_name(&self) -> std::io::Result<String> {
-        if !self.is_part() {
+        if !self.is_sysfs_partition()? {
             return Ok(self.name.clone());
         }
 
@@ -283,6 +315,326 @@ impl BlockDevice {
         // remove new line char
         Ok(s[..s.len() - 1].parse().ok())
     }
+
+    /// Fetch I/O statistics for this block-device.
+    ///
+    /// This relies on `sysfs(5)`, reading the single-line `stat` file documented in
+    /// `Documentation/admin-guide/iostats.rst`. Only the leading columns are indexed, so kernels
+    /// that emit extra trailing fields (e.g. discard/flush counters) are handled fine.
+    ///
+    /// # Errors
+    /// All IO-related failures (including parsing a malformed `stat` file) will be stored in
+    /// [`std::io::Error`].
+    pub fn stats(&self) -> std::io::Result<BlockDevStat> {
+        let s = std::fs::read_to_string(self.sysfs()?.join("stat"))?;
+        let mut fields = s.split_whitespace();
+        let mut next = || {
+            fields.next().and_then(|f| f.parse().ok()).ok_or_else(|| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed stat file")
+            })
+        };
+        let reads_completed = next()?;
+        let _reads_merged = next()?;
+        let sectors_read = next()?;
+        let _time_reading_ms = next()?;
+        let writes_completed = next()?;
+        let _writes_merged = next()?;
+        let sectors_written = next()?;
+        let _time_writing_ms = next()?;
+        let ios_in_progress = next()?;
+        let io_ticks = next()?;
+        Ok(BlockDevStat {
+            reads_completed,
+            sectors_read,
+            writes_completed,
+            sectors_written,
+            ios_in_progress,
+            io_ticks,
+        })
+    }
+
+    /// Read this partition's GPT type GUID directly from its parent disk's partition table,
+    /// without going through `blkid`/udev.
+    ///
+    /// Returns `Ok(None)` if this isn't a partition, if the parent disk isn't GPT-partitioned,
+    /// or if the partition's index can't be determined.
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    pub(crate) fn read_parttype(&self) -> std::io::Result<Option<String>> {
+        use std::io::{Read, Seek, SeekFrom};
+
+        if !self.is_sysfs_partition()? {
+            return Ok(None);
+        }
+        let Some(index) = self.partition_index() else {
+            return Ok(None);
+        };
+
+        let mut disk = std::fs::File::open(Path::new("/dev").join(self.disk_name()?))?;
+
+        let mut header = [0u8; 92];
+        disk.seek(SeekFrom::Start(512))?;
+        disk.read_exact(&mut header)?;
+        if &header[0..8] != b"EFI PART" {
+            return Ok(None);
+        }
+        let entry_lba = u64_le(&header[72..80]);
+        let num_entries = u64::from(u32_le(&header[80..84]));
+        let entry_size = u64::from(u32_le(&header[84..88]));
+        if index == 0 || index as u64 > num_entries {
+            return Ok(None);
+        }
+
+        let Some(base) = entry_lba.checked_mul(512) else {
+            return Ok(None);
+        };
+        let Some(offset) = entry_size.checked_mul(index as u64 - 1) else {
+            return Ok(None);
+        };
+        let Some(entry_offset) = base.checked_add(offset) else {
+            return Ok(None);
+        };
+
+        let mut type_guid = [0u8; 16];
+        disk.seek(SeekFrom::Start(entry_offset))?;
+        disk.read_exact(&mut type_guid)?;
+        Ok((type_guid != [0u8; 16]).then(|| format_guid(&type_guid)))
+    }
+
+    /// Figure out the 1-based partition index of this device within its parent disk, e.g. `1`
+    /// for both `sda1` and `nvme0n1p1`.
+    fn partition_index(&self) -> Option<usize> {
+        let disk = self.disk_name().ok()?;
+        let rest = self.name.strip_prefix(&disk)?;
+        rest.strip_prefix('p').unwrap_or(rest).parse().ok()
+    }
+
+    /// Probe the leading bytes of this device for well-known superblock/boot-sector magic
+    /// numbers to determine its on-disk filesystem type.
+    ///
+    /// Returns `Ok(None)` if none of the known signatures match.
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    pub(crate) fn read_fstype(&self) -> std::io::Result<Option<String>> {
+        use std::io::{Read, Seek, SeekFrom};
+        let mut f = std::fs::File::open(&self.fullname)?;
+        let mut buf = vec![0u8; 0x1_0048].into_boxed_slice();
+        let read = read_as_much_as_possible(&mut f, &mut buf)?;
+        let buf = &buf[..read];
+
+        if buf.len() >= 0x43a && buf[0x438..0x43a] == [0x53, 0xEF] {
+            return Ok(Some("ext4".to_owned()));
+        }
+        if buf.len() >= 4 && &buf[0..4] == b"XFSB" {
+            return Ok(Some("xfs".to_owned()));
+        }
+        if buf.len() >= 0x10048 && &buf[0x10040..0x10048] == b"_BHRfS_M" {
+            return Ok(Some("btrfs".to_owned()));
+        }
+        if buf.len() >= 0x5a && (&buf[0x36..0x39] == b"FAT" || &buf[0x52..0x55] == b"FAT") {
+            return Ok(Some("vfat".to_owned()));
+        }
+
+        // The swap signature sits in the last 10 bytes of the page holding the superblock; only
+        // the conventional 4 KiB page size (offset 0xFF6) is checked.
+        let mut tail = [0u8; 10];
+        if f.seek(SeekFrom::Start(4096 - 10)).is_ok()
+            && f.read_exact(&mut tail).is_ok()
+            && (&tail == b"SWAPSPACE2" || &tail == b"SWAP-SPACE")
+        {
+            return Ok(Some("swap".to_owned()));
+        }
+
+        Ok(None)
+    }
+
+    /// Determine whether this device is currently in use, so callers can refuse destructive
+    /// operations on it instead of corrupting active storage.
+    ///
+    /// A device is busy if it (or, for a disk, one of its [`Self::partitions`]) is mounted, if
+    /// it is active swap space, or if it has at least one [`Self::holders`] entry (i.e.
+    /// something is stacked on top of it, such as a dm-crypt/mdraid/LVM mapping).
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    pub fn is_busy(&self) -> std::io::Result<Option<Busy>> {
+        let mut names = vec![self.fullname.to_string_lossy().into_owned()];
+        if self.is_disk() {
+            names.extend(
+                self.partitions()?
+                    .into_iter()
+                    .map(|part| part.fullname.to_string_lossy().into_owned()),
+            );
+        }
+        let is_self_or_part = |device: &str| names.iter().any(|name| name == device);
+
+        for mount in crate::Mount::list().map_err(std::io::Error::other)? {
+            if is_self_or_part(&mount.device) {
+                return Ok(Some(Busy::Mounted(mount.mountpoint)));
+            }
+        }
+
+        if let Ok(swaps) = std::fs::read_to_string("/proc/swaps") {
+            if swaps
+                .lines()
+                .skip(1)
+                .filter_map(|line| line.split_whitespace().next())
+                .any(is_self_or_part)
+            {
+                return Ok(Some(Busy::Swap));
+            }
+        }
+
+        if let Some(holder) = self.holders()?.into_iter().next() {
+            return Ok(Some(Busy::HeldBy(holder.name)));
+        }
+
+        Ok(None)
+    }
+
+    /// List the devices stacked on top of this one, e.g. a `dm-0` device sitting on top of a
+    /// LUKS partition, or an `md0` array sitting on top of its member disks.
+    ///
+    /// This reads the sysfs `holders/` directory, so it works for any kind of stacking
+    /// (device-mapper, mdraid, multipath, ...) without having to know which one is in play.
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    pub fn holders(&self) -> std::io::Result<Vec<Self>> {
+        Self::devices_in(&self.sysfs()?.join("holders"))
+    }
+
+    /// List the devices this one is built from, e.g. the member partitions of an mdraid array,
+    /// or the backing partition of a dm-crypt mapping.
+    ///
+    /// This reads the sysfs `slaves/` directory and is the inverse of [`Self::holders`].
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    pub fn slaves(&self) -> std::io::Result<Vec<Self>> {
+        Self::devices_in(&self.sysfs()?.join("slaves"))
+    }
+
+    /// List this disk's child partitions by scanning its sysfs directory, the inverse of
+    /// [`Self::disk_name`].
+    ///
+    /// Each subdirectory of [`Self::sysfs`] that itself contains a `partition` file is a
+    /// partition, named after that subdirectory's basename.
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    pub fn partitions(&self) -> std::io::Result<Vec<Self>> {
+        std::fs::read_dir(self.sysfs()?)?
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .is_ok_and(|entry| entry.path().join("partition").exists())
+            })
+            .map(|entry| {
+                Self::from_path_unpopulated(PathBuf::from("/dev").join(entry?.file_name()))
+            })
+            .collect()
+    }
+
+    /// Build a [`Self`] for every entry of a sysfs `holders/`/`slaves/` directory, treating a
+    /// missing directory as "no entries" rather than an error.
+    fn devices_in(dir: &Path) -> std::io::Result<Vec<Self>> {
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        std::fs::read_dir(dir)?
+            .map(|entry| {
+                Self::from_path_unpopulated(PathBuf::from("/dev").join(entry?.file_name()))
+            })
+            .collect()
+    }
+
+    /// Link every device in `devs` to its parents/children (by name) using the stacking
+    /// relationship exposed by [`Self::holders`] and the disk→partition relationship exposed by
+    /// [`Self::partitions`], so a consumer can render a disk→partition→dm→lv hierarchy like the
+    /// `lsblk` tree output.
+    ///
+    /// A device whose `holders/` or `partitions()` cannot be read (e.g. sysfs unavailable) is
+    /// simply treated as a leaf rather than failing the whole tree.
+    #[must_use]
+    pub fn tree(devs: &[Self]) -> DeviceTree {
+        fn link(tree: &mut DeviceTree, parent: &str, child: &str) {
+            tree.children
+                .entry(parent.to_owned())
+                .or_default()
+                .push(child.to_owned());
+            tree.parents
+                .entry(child.to_owned())
+                .or_default()
+                .push(parent.to_owned());
+        }
+
+        let mut tree = DeviceTree {
+            devices: devs.iter().map(|d| (d.name.clone(), d.clone())).collect(),
+            ..DeviceTree::default()
+        };
+        for dev in devs {
+            if let Ok(holders) = dev.holders() {
+                for holder in holders {
+                    link(&mut tree, &dev.name, &holder.name);
+                }
+            }
+            if dev.is_disk() {
+                if let Ok(partitions) = dev.partitions() {
+                    for part in partitions {
+                        link(&mut tree, &dev.name, &part.name);
+                    }
+                }
+            }
+        }
+        tree
+    }
+}
+
+/// I/O statistics for a [`BlockDevice`], as read from sysfs `stat` by [`BlockDevice::stats`].
+///
+/// Sector counts are in 512-byte units, the same convention as [`BlockDevice::capacity`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockDevStat {
+    /// Number of reads completed successfully.
+    pub reads_completed: u64,
+    /// Number of sectors read.
+    pub sectors_read: u64,
+    /// Number of writes completed successfully.
+    pub writes_completed: u64,
+    /// Number of sectors written.
+    pub sectors_written: u64,
+    /// Number of I/Os currently in progress.
+    pub ios_in_progress: u64,
+    /// Milliseconds spent doing I/Os (a measure of device utilization).
+    pub io_ticks: u64,
+}
+
+/// Why a [`BlockDevice`] is considered busy, as reported by [`BlockDevice::is_busy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Busy {
+    /// Mounted at the given path.
+    Mounted(PathBuf),
+    /// Active as swap space.
+    Swap,
+    /// Held by another device (named here), e.g. a dm-crypt/mdraid/LVM mapping on top of it.
+    HeldBy(String),
+}
+
+/// A device-dependency graph linking every device to its parents/children by name, as built by
+/// [`BlockDevice::tree`].
+#[derive(Debug, Clone, Default)]
+pub struct DeviceTree {
+    /// Every device that went into the tree, keyed by its [`BlockDevice::name`].
+    pub devices: HashMap<String, BlockDevice>,
+    /// Maps a device name to the names of the devices stacked on top of it (its holders) and,
+    /// for a disk, to the names of its partitions.
+    pub children: HashMap<String, Vec<String>>,
+    /// Maps a device name to the names of the devices it is built from (its slaves) and, for a
+    /// partition, to the name of its disk.
+    pub parents: HashMap<String, Vec<String>>,
 }
 
 #[cfg(test)]
@@ -297,3 +649,109 @@ fn test_lsblk_smoke() {
         println!("{}", dev.disk_name().unwrap());
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test_format_guid() {
+    // EFI System Partition type GUID, stored mixed-endian on disk: c12a7328-f81f-11d2-ba4b-00a0c93ec93b
+    let bytes: [u8; 16] = [
+        0x28, 0x73, 0x2a, 0xc1, 0x1f, 0xf8, 0xd2, 0x11, 0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9,
+        0x3b,
+    ];
+    assert_eq!(format_guid(&bytes), "c12a7328-f81f-11d2-ba4b-00a0c93ec93b");
+}
+
+/// Write `contents` to a fresh temp file and return a [`BlockDevice`] pointing at it, for
+/// exercising [`BlockDevice::read_fstype`] without needing a real block device.
+#[cfg(test)]
+fn temp_blockdev(name: &str, contents: &[u8]) -> BlockDevice {
+    let path = std::env::temp_dir().join(format!(
+        "lsblk-rs-test-{name}-{}-{:?}",
+        std::process::id(),
+        std::thread::current().id()
+    ));
+    std::fs::write(&path, contents).expect("write temp file");
+    BlockDevice { fullname: path, ..BlockDevice::default() }
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_fstype_ext4() {
+    let mut buf = vec![0u8; 0x43a];
+    buf[0x438] = 0x53;
+    buf[0x439] = 0xEF;
+    let dev = temp_blockdev("ext4", &buf);
+    assert_eq!(dev.read_fstype().expect("read_fstype"), Some("ext4".to_owned()));
+    let _ = std::fs::remove_file(&dev.fullname);
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_fstype_xfs() {
+    let dev = temp_blockdev("xfs", b"XFSB");
+    assert_eq!(dev.read_fstype().expect("read_fstype"), Some("xfs".to_owned()));
+    let _ = std::fs::remove_file(&dev.fullname);
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_fstype_btrfs() {
+    let mut buf = vec![0u8; 0x10048];
+    buf[0x10040..0x10048].copy_from_slice(b"_BHRfS_M");
+    let dev = temp_blockdev("btrfs", &buf);
+    assert_eq!(dev.read_fstype().expect("read_fstype"), Some("btrfs".to_owned()));
+    let _ = std::fs::remove_file(&dev.fullname);
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_fstype_vfat() {
+    let mut buf = vec![0u8; 0x5a];
+    buf[0x36..0x39].copy_from_slice(b"FAT");
+    let dev = temp_blockdev("vfat", &buf);
+    assert_eq!(dev.read_fstype().expect("read_fstype"), Some("vfat".to_owned()));
+    let _ = std::fs::remove_file(&dev.fullname);
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_fstype_swap() {
+    let mut buf = vec![0u8; 4096];
+    buf[4096 - 10..].copy_from_slice(b"SWAPSPACE2");
+    let dev = temp_blockdev("swap", &buf);
+    assert_eq!(dev.read_fstype().expect("read_fstype"), Some("swap".to_owned()));
+    let _ = std::fs::remove_file(&dev.fullname);
+}
+
+#[cfg(test)]
+#[test]
+fn test_read_fstype_unknown() {
+    let dev = temp_blockdev("unknown", &[0u8; 16]);
+    assert_eq!(dev.read_fstype().expect("read_fstype"), None);
+    let _ = std::fs::remove_file(&dev.fullname);
+}
+
+#[cfg(test)]
+#[cfg(feature = "serde")]
+#[test]
+fn test_to_json_uses_lsblk_field_names() {
+    let dev = BlockDevice {
+        name: "sda1".to_owned(),
+        fullname: PathBuf::from("/dev/sda1"),
+        path: Some("pci-0000:00:1f.2-ata-1".to_owned()),
+        parttype: Some("c12a7328-f81f-11d2-ba4b-00a0c93ec93b".to_owned()),
+        fstype: Some("vfat".to_owned()),
+        ..BlockDevice::default()
+    };
+    let json = BlockDevice::to_json(std::slice::from_ref(&dev)).expect("serialize");
+    let value: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    let entry = &value["blockdevices"][0];
+    // `fullname` is the device node path, which is what lsblk -J calls "path"; this crate's own
+    // `path` (the /dev/disk/by-path target) is renamed to "by_path" to avoid colliding with it.
+    assert_eq!(entry["path"], "/dev/sda1");
+    assert_eq!(entry["by_path"], "pci-0000:00:1f.2-ata-1");
+    assert_eq!(entry["parttype"], "c12a7328-f81f-11d2-ba4b-00a0c93ec93b");
+    assert_eq!(entry["fstype"], "vfat");
+    // `None` fields are omitted rather than serialized as `null`.
+    assert!(entry.get("uuid").is_none());
+}
@@ -0,0 +1,130 @@
+//! A caching context for bulk/repeated [`BlockDevice`] lookups.
+use crate::blockdevs::ls_symlinks;
+use crate::{BlockDevice, LsblkError, Res};
+use std::cell::OnceCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn name_of(fullname: &Path) -> String {
+    fullname
+        .strip_prefix("/dev/")
+        .expect("Cannot strip /dev")
+        .to_string_lossy()
+        .to_string()
+}
+
+macro_rules! generate_disk_manage {
+    ($($by:ident)+) => {
+        /// A context that memoizes the `/dev/disk/by-*` symlink maps so that resolving many
+        /// devices is no longer O(devices × directories) of filesystem work.
+        ///
+        /// Each `by-*` directory is only scanned the first time it's needed; subsequent calls to
+        /// [`Self::list`], [`Self::from_path`], or [`Self::populate`] reuse the cached map.
+        #[derive(Debug, Default)]
+        pub struct DiskManage {
+            $($by: OnceCell<HashMap<PathBuf, String>>,)+
+        }
+
+        impl DiskManage {
+            $(
+                fn $by(&self) -> Res<&HashMap<PathBuf, String>> {
+                    if self.$by.get().is_none() {
+                        let map = ls_symlinks(Path::new(concat!("/dev/disk/by-", stringify!($by))))?
+                            .collect::<Res<HashMap<_, _>>>()?;
+                        let _ = self.$by.set(map);
+                    }
+                    Ok(self.$by.get().expect("just populated the cell above"))
+                }
+            )+
+
+            /// Populate every cacheable field of `dev` (i.e. everything [`crate::Populate`]
+            /// would populate) from the cached `/dev/disk/by-*` maps.
+            ///
+            /// # Errors
+            /// There are no particular errors other than IO / symlink resolution failures, etc.
+            pub fn populate(&self, dev: &mut BlockDevice) -> Res<()> {
+                $(
+                    if let Some(blk) = self.$by()?.get(&dev.fullname) {
+                        dev.$by = Some(blk.clone());
+                    }
+                )+
+                Ok(())
+            }
+        }
+    };
+}
+
+generate_disk_manage!(diskseq path uuid partuuid label partlabel id);
+
+impl DiskManage {
+    /// Create a new, empty caching context. No directories are scanned until a lookup actually
+    /// needs them.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List out all found block devices and populate all cacheable fields, reusing the cached
+    /// `/dev/disk/by-*` maps across calls.
+    ///
+    /// # Errors
+    /// There are no particular errors other than IO / symlink resolution failures, etc.
+    pub fn list(&self) -> Res<Vec<BlockDevice>> {
+        let mut result: HashMap<String, BlockDevice> = HashMap::new();
+        for (fullname, blk) in self.diskseq()? {
+            let name = name_of(fullname);
+            result.insert(
+                name.clone(),
+                BlockDevice {
+                    name,
+                    fullname: fullname.clone(),
+                    diskseq: Some(blk.clone()),
+                    ..BlockDevice::default()
+                },
+            );
+        }
+        macro_rules! insert {
+            ($by:ident) => {
+                for (fullname, blk) in self.$by()? {
+                    let name = name_of(fullname);
+                    result
+                        .entry(name)
+                        .or_insert_with(|| BlockDevice::from_abs_path_unpopulated(fullname.clone()))
+                        .$by = Some(blk.clone());
+                }
+            };
+        }
+        insert!(path);
+        insert!(uuid);
+        insert!(partuuid);
+        insert!(label);
+        insert!(partlabel);
+        insert!(id);
+        Ok(result.into_values().collect())
+    }
+
+    /// Create a [`BlockDevice`] from a path that is either `/dev/{name}` or a path to a
+    /// (sym)link that points to `/dev/{name}`, populated from the cached `/dev/disk/by-*` maps.
+    ///
+    /// # Panics
+    /// If somehow this isn't in `/dev/`, the function panics.
+    ///
+    /// # Errors
+    /// There are no particular errors other than IO / symlink resolution failures, etc.
+    pub fn from_path<P: AsRef<Path>>(&self, p: P) -> Result<BlockDevice, LsblkError> {
+        let pathbuf = p
+            .as_ref()
+            .canonicalize()
+            .map_err(|e| LsblkError::BadSymlink(p.as_ref().to_owned(), e))?;
+        let mut res = BlockDevice::from_abs_path_unpopulated(pathbuf);
+        self.populate(&mut res)?;
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_name_of() {
+    assert_eq!(name_of(Path::new("/dev/sda1")), "sda1");
+    assert_eq!(name_of(Path::new("/dev/nvme0n1p1")), "nvme0n1p1");
+}
@@ -1,9 +1,41 @@
-use std::{io::BufRead, path::PathBuf};
+use std::path::PathBuf;
+
+#[cfg(target_os = "linux")]
+use std::{ffi::OsString, io::BufRead, os::unix::ffi::OsStrExt};
 
 use crate::Res;
 
+/// Decode the octal escape sequences (`\040`, `\011`, `\012`, `\134`) that the kernel uses in
+/// `/proc/mounts` and `/proc/self/mountinfo` to hide spaces, tabs, newlines, and backslashes in
+/// device and mountpoint fields.
+///
+/// The result goes through [`OsStrExt::from_bytes`] rather than `String` because an unescaped
+/// path is not guaranteed to be valid UTF-8.
+#[cfg(target_os = "linux")]
+fn decode_mount_escapes(s: &str) -> OsString {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() {
+            let value = bytes[i + 1..i + 4].iter().try_fold(0u32, |acc, &d| {
+                (b'0'..=b'7').contains(&d).then(|| acc * 8 + u32::from(d - b'0'))
+            });
+            if let Some(b) = value.and_then(|v| u8::try_from(v).ok()) {
+                out.push(b);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    std::ffi::OsStr::from_bytes(&out).to_owned()
+}
+
 /// Represent a mountpoint
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mount {
     /// The device name (either a path or something like zram0)
     pub device: String,
@@ -15,6 +47,7 @@ pub struct Mount {
     pub mountopts: String,
 }
 
+#[cfg(target_os = "linux")]
 impl Mount {
     /// List out all mountpoints and populate all fields.
     ///
@@ -50,14 +83,148 @@ impl Mount {
             .filter_map(|l| {
                 let mut parts = l.trim_end_matches(" 0 0").split(' ');
                 Some(Self {
-                    device: parts.next()?.into(),
-                    mountpoint: parts.next()?.into(),
+                    device: decode_mount_escapes(parts.next()?).to_string_lossy().into_owned(),
+                    mountpoint: decode_mount_escapes(parts.next()?).into(),
                     fstype: parts.next()?.into(),
                     mountopts: parts.next()?.into(),
                 })
             })
         )
     }
+}
+
+#[cfg(target_os = "macos")]
+impl Mount {
+    /// List out all mountpoints and populate all fields, using `getmntinfo(3)`.
+    ///
+    /// macOS has no per-mount equivalent of `/proc/mounts`' `fs_mntopts` column, so
+    /// [`Self::mountopts`] is always left empty here.
+    ///
+    /// # Errors
+    /// Returns [`crate::LsblkError::Syscall`] if `getmntinfo` reports a failure.
+    pub fn list() -> Res<impl Iterator<Item = Self>> {
+        // SAFETY: `getmntinfo` either returns 0 on failure (checked below) or sets `buf` to
+        // point at a kernel-owned array of `n` initialized `statfs` entries that remains valid
+        // for the life of the process; we only read out of it through `from_raw_parts`.
+        let (buf, n) = unsafe {
+            let mut buf: *mut libc::statfs = std::ptr::null_mut();
+            let n = libc::getmntinfo(&mut buf, libc::MNT_NOWAIT);
+            (buf, n)
+        };
+        if n <= 0 {
+            return Err(crate::LsblkError::Syscall(
+                "getmntinfo",
+                std::io::Error::last_os_error(),
+            ));
+        }
+        // SAFETY: see above; `n` is positive here.
+        let entries = unsafe { std::slice::from_raw_parts(buf, n as usize) };
+        let mounts = entries
+            .iter()
+            .map(|e| {
+                // SAFETY: the kernel NUL-terminates these fixed-size C string fields.
+                let to_string = |s: &[libc::c_char]| unsafe {
+                    std::ffi::CStr::from_ptr(s.as_ptr()).to_string_lossy().into_owned()
+                };
+                Self {
+                    device: to_string(&e.f_mntfromname),
+                    mountpoint: PathBuf::from(to_string(&e.f_mntonname)),
+                    fstype: to_string(&e.f_fstypename),
+                    mountopts: String::new(),
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(mounts.into_iter())
+    }
+}
+
+#[cfg(windows)]
+impl Mount {
+    /// List out all mountpoints and populate all fields, enumerating volumes with
+    /// `FindFirstVolumeW`/`FindNextVolumeW` and resolving their mount points with
+    /// `GetVolumePathNamesForVolumeNameW`.
+    ///
+    /// Windows has no equivalent of `/proc/mounts`' per-mount `fs_mntopts` column, so
+    /// [`Self::mountopts`] is always left empty here. [`Self::fstype`] is not queried and is
+    /// also left empty. [`Self::device`] holds the volume GUID
+    /// path (e.g. `\\?\Volume{...}\`) since a volume may be mounted at more than one path.
+    ///
+    /// # Errors
+    /// Returns [`crate::LsblkError::Syscall`] if volume enumeration fails.
+    pub fn list() -> Res<impl Iterator<Item = Self>> {
+        use windows_sys::Win32::Foundation::{ERROR_NO_MORE_FILES, INVALID_HANDLE_VALUE};
+        use windows_sys::Win32::Storage::FileSystem::{
+            FindFirstVolumeW, FindNextVolumeW, FindVolumeClose, GetVolumePathNamesForVolumeNameW,
+        };
+
+        let mut mounts = Vec::new();
+        let mut volume_name = [0u16; 260];
+        // SAFETY: `volume_name` is a correctly sized, writable UTF-16 buffer; the returned
+        // handle is checked before any further use.
+        let handle = unsafe {
+            FindFirstVolumeW(volume_name.as_mut_ptr(), volume_name.len() as u32)
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(crate::LsblkError::Syscall(
+                "FindFirstVolumeW",
+                std::io::Error::last_os_error(),
+            ));
+        }
+        loop {
+            let mut path_names = [0u16; 4096];
+            let mut returned_len = 0u32;
+            // SAFETY: `handle` is the live search handle from `FindFirstVolumeW`/
+            // `FindNextVolumeW`; `volume_name` is NUL-terminated by the Win32 API, and
+            // `path_names` is a correctly sized, writable UTF-16 buffer.
+            let ok = unsafe {
+                GetVolumePathNamesForVolumeNameW(
+                    volume_name.as_ptr(),
+                    path_names.as_mut_ptr(),
+                    path_names.len() as u32,
+                    &mut returned_len,
+                )
+            };
+            if ok != 0 {
+                let nul = volume_name.iter().position(|&c| c == 0).unwrap_or(volume_name.len());
+                let device = String::from_utf16_lossy(&volume_name[..nul]);
+                for path in decode_multi_sz(&path_names) {
+                    mounts.push(Self {
+                        device: device.clone(),
+                        mountpoint: PathBuf::from(path),
+                        fstype: String::new(),
+                        mountopts: String::new(),
+                    });
+                }
+            }
+            // SAFETY: `handle` is the live search handle from `FindFirstVolumeW`.
+            let has_next = unsafe {
+                FindNextVolumeW(handle, volume_name.as_mut_ptr(), volume_name.len() as u32)
+            };
+            if has_next == 0 {
+                let err = std::io::Error::last_os_error();
+                // SAFETY: `handle` is still open and has not been closed yet.
+                unsafe { FindVolumeClose(handle) };
+                if err.raw_os_error() != Some(ERROR_NO_MORE_FILES as i32) {
+                    return Err(crate::LsblkError::Syscall("FindNextVolumeW", err));
+                }
+                break;
+            }
+        }
+        Ok(mounts.into_iter())
+    }
+}
+
+/// Split a Win32 "multi-string" (a run of NUL-terminated UTF-16 strings, itself terminated by
+/// an empty string) into its individual entries.
+#[cfg(windows)]
+fn decode_multi_sz(buf: &[u16]) -> Vec<String> {
+    buf.split(|&c| c == 0)
+        .take_while(|s| !s.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect()
+}
+
+impl Mount {
     /// List out the mounting options (`fs_mntopts`).
     ///
     /// This returns an iterator of (key, optional value).
@@ -85,6 +252,100 @@ impl Mount {
     }
 }
 
+/// A single entry of `/proc/self/mountinfo`, richer than [`Mount`] because it also exposes the
+/// mount tree (via `mount_id`/`parent_id`) and propagation state, none of which
+/// `/proc/mounts` carries.
+///
+/// For more information, visit
+/// [`proc_pid_mountinfo(5)`](https://man.archlinux.org/man/proc_pid_mountinfo.5.en).
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MountInfo {
+    /// Unique identifier of this mount (may be reused after a `umount`).
+    pub mount_id: u32,
+    /// The mount id of the parent mount, or of this mount itself for the root of a mount
+    /// namespace.
+    pub parent_id: u32,
+    /// Major/minor device number of the filesystem backing this mount.
+    pub major_minor: (u32, u32),
+    /// The pathname of the directory in the filesystem that forms the root of this mount.
+    pub root: PathBuf,
+    /// The mountpoint relative to the process's root directory.
+    pub mountpoint: PathBuf,
+    /// Per-mount options, e.g. `rw,noatime` (as opposed to [`Self::super_options`], which are
+    /// per-superblock).
+    pub mount_options: String,
+    /// Optional propagation fields, verbatim, e.g. `shared:2`, `master:3`, `propagate_from:4`,
+    /// or `unbindable`. Empty for a private mount.
+    pub optional_fields: Vec<String>,
+    /// Filesystem type.
+    pub fstype: String,
+    /// Mount source, e.g. the device.
+    pub source: String,
+    /// Per-superblock options, shared by every mount of the same filesystem (as opposed to
+    /// [`Self::mount_options`], which are per-mount).
+    pub super_options: String,
+}
+
+#[cfg(target_os = "linux")]
+impl Mount {
+    /// List out all mountpoints with their full mount-tree information.
+    ///
+    /// Unlike [`Self::list`], which only reads `/proc/mounts`, this reads `/proc/self/mountinfo`
+    /// so that bind mounts, mount propagation, and the mount tree (via `mount_id`/`parent_id`)
+    /// can be told apart.
+    ///
+    /// # Errors
+    /// Since this function depends on the existence of `/proc/self/mountinfo`, failures to open
+    /// the file will cause [`crate::LsblkError::ReadFile`].
+    ///
+    /// # Caveats
+    /// If for some reason `/proc/self/mountinfo` is not formatted properly, the iterator will
+    /// skip those lines.
+    #[rustfmt::skip] //? https://github.com/rust-lang/rustfmt/issues/3157#issuecomment-2213427895
+    pub fn list_info() -> Res<impl Iterator<Item = MountInfo>> {
+        Ok(
+            std::io::BufReader::new(
+                std::fs::File::open(PathBuf::from("/proc/self/mountinfo"))
+                    .map_err(|e| crate::LsblkError::ReadFile("/proc/self/mountinfo".into(), e))?,
+            )
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|l| {
+                let mut parts = l.split(' ');
+                let mount_id = parts.next()?.parse().ok()?;
+                let parent_id = parts.next()?.parse().ok()?;
+                let (major, minor) = parts.next()?.split_once(':')?;
+                let major_minor = (major.parse().ok()?, minor.parse().ok()?);
+                let root = decode_mount_escapes(parts.next()?).into();
+                let mountpoint = decode_mount_escapes(parts.next()?).into();
+                let mount_options = parts.next()?.to_owned();
+                let optional_fields = parts
+                    .by_ref()
+                    .take_while(|&field| field != "-")
+                    .map(ToOwned::to_owned)
+                    .collect();
+                let fstype = parts.next()?.into();
+                let source = decode_mount_escapes(parts.next()?).to_string_lossy().into_owned();
+                let super_options = parts.next()?.to_owned();
+                Some(MountInfo {
+                    mount_id,
+                    parent_id,
+                    major_minor,
+                    root,
+                    mountpoint,
+                    mount_options,
+                    optional_fields,
+                    fstype,
+                    source,
+                    super_options,
+                })
+            })
+        )
+    }
+}
+
 #[test]
 fn test_list_mountpoints() -> Res<()> {
     for x in Mount::list()? {
@@ -92,3 +353,23 @@ fn test_list_mountpoints() -> Res<()> {
     }
     Ok(())
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_list_mountinfo() -> Res<()> {
+    for x in Mount::list_info()? {
+        println!("{x:?}");
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_decode_mount_escapes() {
+    assert_eq!(decode_mount_escapes("/mnt/my\\040drive"), "/mnt/my drive");
+    assert_eq!(decode_mount_escapes("a\\011b\\012c\\134d"), "a\tb\nc\\d");
+    assert_eq!(decode_mount_escapes("/mnt/plain"), "/mnt/plain");
+    // Out-of-range 3-digit octal sequences (>= \400) aren't byte escapes; pass them through
+    // literally instead of overflowing.
+    assert_eq!(decode_mount_escapes("/mnt/a\\777b"), "/mnt/a\\777b");
+}
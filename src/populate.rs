@@ -43,3 +43,36 @@ impl Populate for BlockDevice {
         self
     }
 }
+
+/// Populates fields that aren't resolved via `/dev/disk/` symlinks, but by reading the device's
+/// on-disk data directly.
+pub trait PopulateDisk: Populate {
+    /// Populate the GPT partition type GUID by reading the parent disk's GPT header and
+    /// partition entry array directly.
+    ///
+    /// This could be expensive depending on where on the disk the partition entry array sits.
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    fn populate_parttype(&mut self) -> std::io::Result<Option<&str>> {
+        let parttype = self.as_ref().read_parttype()?;
+        self.as_mut().parttype = parttype;
+        Ok(self.as_ref().parttype.as_deref())
+    }
+
+    /// Populate the on-disk filesystem type by probing the device for well-known superblock
+    /// signatures.
+    ///
+    /// This could be expensive depending on the amount of data that needs to be read off the
+    /// device to find a matching signature.
+    ///
+    /// # Errors
+    /// All IO-related failures will be stored in [`std::io::Error`].
+    fn populate_fstype(&mut self) -> std::io::Result<Option<&str>> {
+        let fstype = self.as_ref().read_fstype()?;
+        self.as_mut().fstype = fstype;
+        Ok(self.as_ref().fstype.as_deref())
+    }
+}
+
+impl PopulateDisk for BlockDevice {}